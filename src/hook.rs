@@ -2,16 +2,21 @@ use std::collections::HashMap;
 use std::fmt::Display;
 use std::ops::Deref;
 use std::path::PathBuf;
+use std::sync::Arc;
 
 use anyhow::Result;
 use futures::stream::FuturesUnordered;
 use futures::StreamExt;
+use serde::Serialize;
 use thiserror::Error;
 use url::Url;
 
-use crate::config::{self, read_config, read_manifest, ConfigLocalHook, ConfigLocalRepo, ConfigRemoteHook, ConfigRemoteRepo, ConfigRepo, ConfigWire, ManifestHook, CONFIG_FILE, MANIFEST_FILE};
+use crate::config::{self, read_config, read_manifest, ConfigLocalHook, ConfigLocalRepo, ConfigMetaRepo, ConfigRemoteHook, ConfigRemoteRepo, ConfigRepo, ConfigWire, ManifestHook, CONFIG_FILE, MANIFEST_FILE};
 use crate::fs::CWD;
+use crate::languages::meta;
+use crate::lock::Lockfile;
 use crate::store::Store;
+use crate::vcs::{self, Backend};
 
 #[derive(Debug, Error)]
 pub enum Error {
@@ -31,6 +36,8 @@ pub struct RemoteRepo {
     path: PathBuf,
     url: Url,
     rev: String,
+    /// The VCS backend that cloned and checked out this repo.
+    backend: Arc<dyn Backend>,
     hooks: HashMap<String, ManifestHook>,
 }
 
@@ -40,16 +47,31 @@ pub struct LocalRepo {
     hooks: HashMap<String, ConfigLocalHook>,
 }
 
+#[derive(Debug)]
+pub struct MetaRepo {
+    hooks: HashMap<String, ManifestHook>,
+}
+
 #[derive(Debug)]
 pub enum Repo {
     Remote(RemoteRepo),
     Local(LocalRepo),
-    Meta,
+    Meta(MetaRepo),
 }
 
+/// Ids of the built-in `language: meta` hooks, synthesized entirely
+/// in-process: no clone, no environment.
+pub const CHECK_HOOKS_APPLY: &str = "check-hooks-apply";
+pub const CHECK_USELESS_EXCLUDES: &str = "check-useless-excludes";
+pub const IDENTITY: &str = "identity";
+
 impl Repo {
-    pub fn remote(url: &str, rev: &str, path: &str) -> Result<Self> {
+    /// `repo_type` forces a specific [`vcs::Backend`] by name (e.g. `"hg"`),
+    /// taking precedence over guessing one from `url`'s scheme; pass `None`
+    /// to always guess. See `vcs::select` for the exact precedence.
+    pub fn remote(url: &str, rev: &str, path: &str, repo_type: Option<&str>) -> Result<Self> {
         let url = Url::parse(&url).map_err(Error::InvalidUrl)?;
+        let backend = vcs::select(&url, repo_type)?;
 
         let path = PathBuf::from(path);
         let path = path.join(MANIFEST_FILE);
@@ -64,6 +86,7 @@ impl Repo {
             path,
             url,
             rev: rev.to_string(),
+            backend,
             hooks,
         }))
     }
@@ -79,14 +102,51 @@ impl Repo {
     }
 
     pub fn meta() -> Self {
-        todo!()
+        let synthesize = |id: &str, name: &str| ManifestHook {
+            id: id.to_string(),
+            name: name.to_string(),
+            entry: id.to_string(),
+            language: config::Language::Meta,
+            ..Default::default()
+        };
+
+        let hooks = [
+            synthesize(CHECK_HOOKS_APPLY, "Check hooks apply to the repository"),
+            synthesize(CHECK_USELESS_EXCLUDES, "Check for useless excludes"),
+            synthesize(IDENTITY, "identity"),
+        ]
+        .into_iter()
+        .map(|hook| (hook.id.clone(), hook))
+        .collect();
+
+        Self::Meta(MetaRepo { hooks })
     }
 
     pub fn get_hook(&self, id: &str) -> Option<&ManifestHook> {
         match self {
             Repo::Remote(repo) => repo.hooks.get(id),
             Repo::Local(repo) => repo.hooks.get(id),
-            Repo::Meta => None,
+            Repo::Meta(repo) => repo.hooks.get(id),
+        }
+    }
+
+    /// Name of the VCS backend that produced this repo (`"git"`, `"hg"`),
+    /// or `None` for repos that aren't fetched from a VCS at all.
+    pub fn backend_name(&self) -> Option<&'static str> {
+        match self {
+            Repo::Remote(repo) => Some(repo.backend.name()),
+            Repo::Local(_) | Repo::Meta(_) => None,
+        }
+    }
+
+    /// Concrete commit id that `rev` currently points to on the remote,
+    /// looked up through the repo's VCS backend (not read from the local
+    /// checkout). `rev` itself may be a branch or tag name, so this is what
+    /// actually gets pinned in `.pre-commit-lock`.
+    pub async fn resolve_commit(&self) -> Result<Option<String>> {
+        match self {
+            Repo::Remote(repo) => Ok(Some(repo.backend.resolve_rev(&repo.url, &repo.rev).await?)),
+            Repo::Local(_) | Repo::Meta(_) => Ok(None),
         }
     }
 }
@@ -96,7 +156,7 @@ impl Display for Repo {
         match self {
             Repo::Remote(repo) => write!(f, "{}@{}", repo.url, repo.rev),
             Repo::Local(_) => write!(f, "local"),
-            Repo::Meta => write!(f, "meta"),
+            Repo::Meta(_) => write!(f, "meta"),
         }
     }
 }
@@ -126,20 +186,81 @@ impl Project {
     //         .collect::<Result<_>>()
     // }
 
+    /// Resolve every hook, reusing `.pre-commit-lock` (if present and still
+    /// consistent with `config.yaml`) to pin each remote repo to the same
+    /// commit it was resolved to last time. `additional_dependencies` are
+    /// recorded as configured; their versions are not pinned.
     pub async fn hooks(&self, store: &Store) -> Result<Vec<Hook>> {
+        self.resolve_hooks(store, false).await
+    }
+
+    /// Like [`Project::hooks`], but ignores any existing `.pre-commit-lock`
+    /// and re-resolves every repo's `rev` against upstream, then overwrites
+    /// the lockfile with the freshly resolved commits and dependencies.
+    pub async fn refresh_lock(&self, store: &Store) -> Result<Vec<Hook>> {
+        self.resolve_hooks(store, true).await
+    }
+
+    async fn resolve_hooks(&self, store: &Store, refresh: bool) -> Result<Vec<Hook>> {
+        let lockfile = if refresh {
+            Lockfile::default()
+        } else {
+            Lockfile::read(&self.root)?.unwrap_or_default()
+        };
+
         let mut hooks = Vec::new();
+        let mut meta_hook_configs = Vec::new();
 
         // TODO: progress bar
-        // Prepare repos.
+        // Prepare repos, pinning remote repos to their locked commit when
+        // the lockfile has one for the configured `url`/`rev`.
         let mut tasks = FuturesUnordered::new();
         for repo_config in &self.config.repos {
-            tasks.push(async { (repo_config, store.prepare_repo(repo_config, None).await) });
+            let locked_commit = match repo_config {
+                ConfigRepo::Remote(remote) => lockfile.locked_commit(&remote.url, &remote.rev).map(str::to_string),
+                ConfigRepo::Local(_) | ConfigRepo::Meta(_) => None,
+            };
+            let effective_config = match (repo_config, &locked_commit) {
+                (ConfigRepo::Remote(remote), Some(commit)) => ConfigRepo::Remote(ConfigRemoteRepo {
+                    rev: commit.clone(),
+                    ..remote.clone()
+                }),
+                _ => repo_config.clone(),
+            };
+            tasks.push(async move {
+                let repo = store.prepare_repo(&effective_config, None).await;
+                (repo_config, effective_config, locked_commit, repo)
+            });
         }
 
         let mut hook_tasks = FuturesUnordered::new();
+        let mut new_lockfile = Lockfile::default();
 
-        while let Some((repo_config, repo)) = tasks.next().await {
+        while let Some((repo_config, effective_config, locked_commit, repo)) = tasks.next().await {
             let repo = repo?;
+            // Pin against the `rev` written in `config.yaml`, not whatever
+            // commit we may have just substituted it with above — that's
+            // what a later run will look `rev` up by.
+            let configured_remote = match repo_config {
+                ConfigRepo::Remote(remote) => Some((remote.url.as_str(), remote.rev.as_str())),
+                ConfigRepo::Local(_) | ConfigRepo::Meta(_) => None,
+            };
+            if let Some((url, rev)) = configured_remote {
+                // `effective_config.rev` is already the resolved commit when
+                // `locked_commit` is set, so re-resolving it through the VCS
+                // backend would ask it to look up a full SHA as if it were a
+                // ref (`git ls-remote <url> <sha>` doesn't match anything).
+                // Only ask the backend to resolve `rev` when we don't already
+                // know the commit it points to.
+                let commit = match locked_commit {
+                    Some(commit) => Some(commit),
+                    None => repo.resolve_commit().await?,
+                };
+                if let Some(commit) = commit {
+                    new_lockfile.pin_repo(url, rev, &commit);
+                }
+            }
+
             match repo_config {
                 ConfigRepo::Remote(ConfigRemoteRepo { hooks: remote_hooks, .. }) => {
                     for hook_config in remote_hooks {
@@ -155,8 +276,31 @@ impl Project {
                         hook.update(hook_config.clone());
                         hook.fill(&self.config);
 
+                        // Reuse the exact `additional_dependencies` this hook
+                        // resolved to last time, the same way its repo's `rev`
+                        // gets pinned to a locked `commit` above — but only
+                        // while `config.yaml` still asks for the same deps the
+                        // lockfile recorded. Pinning on `is_some()` alone would
+                        // reapply the stale locked list even after the user
+                        // edited `additional_dependencies`, silently undoing
+                        // their edit; comparing against what's configured now
+                        // keeps the lockfile a cache of the current config,
+                        // not an override of it.
+                        if !refresh {
+                            if let Some((url, rev)) = configured_remote {
+                                if let Some(locked) = lockfile.locked_dependencies(url, rev, &hook.id) {
+                                    if hook.additional_dependencies.as_deref() == Some(locked) {
+                                        hook.pin_additional_dependencies(locked.to_vec());
+                                    }
+                                }
+                            }
+                        }
+
                         if let Some(deps) = &hook.additional_dependencies {
-                            hook_tasks.push(store.prepare_repo(repo_config, Some(deps.clone())));
+                            hook_tasks.push(store.prepare_repo(&effective_config, Some(deps.clone())));
+                        }
+                        if let Some((url, rev)) = configured_remote {
+                            new_lockfile.record_hook(url, rev, hook.additional_dependencies.as_deref().unwrap_or_default(), &hook.id);
                         }
 
                         hooks.push(hook);
@@ -174,23 +318,67 @@ impl Project {
                         hooks.push(hook);
                     }
                 }
-                ConfigRepo::Meta(_) => {}
+                ConfigRepo::Meta(ConfigMetaRepo { hooks: meta_hooks, .. }) => {
+                    meta_hook_configs.extend(meta_hooks.iter().cloned());
+                }
             }
         }
 
         // Prepare hooks with `additional_dependencies` (they need separate repos).
         hook_tasks.collect().await?;
 
+        // `check-hooks-apply`/`check-useless-excludes` inspect the fully
+        // resolved hook set, so the meta repo's hooks are only built once
+        // every other repo above has been resolved.
+        if !meta_hook_configs.is_empty() {
+            let meta_repo = Repo::meta();
+            let check_hooks_apply_report = meta::check_hooks_apply(&hooks);
+            let check_useless_excludes_report = meta::check_useless_excludes(&hooks);
+
+            for hook_config in meta_hook_configs {
+                let Some(manifest_hook) = meta_repo.get_hook(&hook_config.id) else {
+                    return Err(Error::HookNotFound {
+                        hook: hook_config.id.clone(),
+                        repo: meta_repo.to_string(),
+                    })?;
+                };
+
+                let mut hook = Hook::from(manifest_hook.clone());
+                hook.update(hook_config);
+                hook.fill(&self.config);
+
+                match hook.id.as_str() {
+                    CHECK_HOOKS_APPLY => hook.set_meta_report(check_hooks_apply_report.clone()),
+                    CHECK_USELESS_EXCLUDES => hook.set_meta_report(check_useless_excludes_report.clone()),
+                    _ => {}
+                }
+
+                hooks.push(hook);
+            }
+        }
+
+        new_lockfile.write(&self.root)?;
+
         Ok(hooks)
     }
 }
 
 #[derive(Debug)]
-pub struct Hook(ManifestHook);
+pub struct Hook(ManifestHook, Option<String>);
 
 impl From<ManifestHook> for Hook {
     fn from(hook: ManifestHook) -> Self {
-        Self(hook)
+        Self(hook, None)
+    }
+}
+
+/// Serializes as the underlying `ManifestHook` alone: the `meta_report`
+/// slot is in-process state for the built-in meta hooks, not part of a
+/// hook's definition, so wasm plugins (which receive this JSON as their
+/// `install`/`run` argument) never see it.
+impl Serialize for Hook {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        self.0.serialize(serializer)
     }
 }
 
@@ -247,6 +435,13 @@ impl Hook {
         }
     }
 
+    /// Replace `additional_dependencies` with the exact set recorded in
+    /// `.pre-commit-lock` on a previous run, the same way a remote repo's
+    /// `rev` gets pinned to its locked `commit`.
+    pub fn pin_additional_dependencies(&mut self, deps: Vec<String>) {
+        self.0.additional_dependencies = Some(deps);
+    }
+
     pub fn fill(&mut self, config: &ConfigWire) {
         let language = self.0.language;
         if self.0.language_version.is_none() {
@@ -265,4 +460,15 @@ impl Hook {
 
         // TODO: check ENVIRONMENT_DIR with language_version and additional_dependencies
     }
+
+    /// Attach a precomputed `check-hooks-apply`/`check-useless-excludes`
+    /// report, empty on success. Only meaningful for `language: meta` hooks;
+    /// every other hook leaves this unset and runs normally.
+    pub fn set_meta_report(&mut self, report: String) {
+        self.1 = Some(report);
+    }
+
+    pub fn meta_report(&self) -> Option<&str> {
+        self.1.as_deref()
+    }
 }
@@ -0,0 +1,216 @@
+use std::fmt::{self, Display};
+use std::process::Output;
+
+use anyhow::Result;
+use regex::Regex;
+
+use crate::config;
+use crate::fs::CWD;
+use crate::hook::{Hook, CHECK_HOOKS_APPLY, CHECK_USELESS_EXCLUDES, IDENTITY};
+
+use super::DEFAULT_VERSION;
+
+/// The `language: meta` backend: hooks that run entirely in-process against
+/// already-resolved config, with no clone and no environment of their own.
+#[derive(Debug, Copy, Clone)]
+pub struct Meta;
+
+impl Display for Meta {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "meta")
+    }
+}
+
+impl Meta {
+    pub fn name(self) -> config::Language {
+        config::Language::Meta
+    }
+
+    pub fn default_version(&self) -> &str {
+        DEFAULT_VERSION
+    }
+
+    pub fn environment_dir(&self) -> Option<&str> {
+        None
+    }
+
+    pub async fn install(&self, _hook: &Hook) -> Result<()> {
+        Ok(())
+    }
+
+    pub async fn check_health(&self) -> Result<()> {
+        Ok(())
+    }
+
+    pub async fn run(&self, hook: &Hook, filenames: &[&String]) -> Result<Output> {
+        let (code, stdout) = match hook.id.as_str() {
+            CHECK_HOOKS_APPLY | CHECK_USELESS_EXCLUDES => match hook.meta_report() {
+                Some(report) if !report.is_empty() => (1, report.as_bytes().to_vec()),
+                _ => (0, Vec::new()),
+            },
+            IDENTITY => {
+                let joined = filenames.iter().map(String::as_str).collect::<Vec<_>>().join("\n");
+                (0, joined.into_bytes())
+            }
+            id => anyhow::bail!("unknown meta hook `{id}`"),
+        };
+
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(code << 8);
+        #[cfg(not(unix))]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(code as u32);
+
+        Ok(Output {
+            status,
+            stdout,
+            stderr: Vec::new(),
+        })
+    }
+}
+
+/// Report, one line per offender, of hooks in `hooks` whose `files`/`types`
+/// filters match nothing in the repository. Empty string means every hook
+/// applies to at least one file.
+pub fn check_hooks_apply(hooks: &[Hook]) -> String {
+    let files = repo_files();
+    let mut report = String::new();
+
+    for hook in hooks {
+        if hook.id == CHECK_HOOKS_APPLY || hook.id == CHECK_USELESS_EXCLUDES || hook.id == IDENTITY {
+            continue;
+        }
+
+        let files_re = hook.files.as_deref().and_then(|pattern| Regex::new(pattern).ok());
+        let exclude_re = hook.exclude.as_deref().and_then(|pattern| Regex::new(pattern).ok());
+
+        let matches_any = files.iter().any(|path| {
+            let included = files_re.as_ref().map_or(true, |re| re.is_match(path));
+            let excluded = exclude_re.as_ref().map_or(false, |re| re.is_match(path));
+            included && !excluded && matches_type_filters(hook.types.as_deref(), hook.types_or.as_deref(), hook.exclude_types.as_deref(), &file_types(path))
+        });
+
+        if !matches_any {
+            report.push_str(&format!("{} does not apply to this repository\n", hook.id));
+        }
+    }
+
+    report
+}
+
+/// Report of `exclude` patterns in `hooks` that don't actually exclude
+/// anything `files` would otherwise have matched. Empty string means every
+/// `exclude` is load-bearing.
+pub fn check_useless_excludes(hooks: &[Hook]) -> String {
+    let files = repo_files();
+    let mut report = String::new();
+
+    for hook in hooks {
+        if hook.id == CHECK_HOOKS_APPLY || hook.id == CHECK_USELESS_EXCLUDES || hook.id == IDENTITY {
+            continue;
+        }
+
+        let Some(exclude_re) = hook.exclude.as_deref().and_then(|pattern| Regex::new(pattern).ok()) else {
+            continue;
+        };
+        let files_re = hook.files.as_deref().and_then(|pattern| Regex::new(pattern).ok());
+
+        let excludes_anything = files.iter().any(|path| {
+            files_re.as_ref().map_or(true, |re| re.is_match(path))
+                && matches_type_filters(hook.types.as_deref(), hook.types_or.as_deref(), hook.exclude_types.as_deref(), &file_types(path))
+                && exclude_re.is_match(path)
+        });
+
+        if !excludes_anything {
+            report.push_str(&format!("`{}`'s exclude pattern is useless (matches no files)\n", hook.id));
+        }
+    }
+
+    report
+}
+
+/// Whether a hook's `types`/`types_or`/`exclude_types` filters (`files`/
+/// `exclude` are handled separately, by regex) let a file tagged `tags`
+/// through: all of `types` must be present, at least one of `types_or`
+/// must be present, and none of `exclude_types` may be present. An absent
+/// filter list imposes no constraint, matching pre-commit's own semantics.
+fn matches_type_filters(types: Option<&[String]>, types_or: Option<&[String]>, exclude_types: Option<&[String]>, tags: &[&str]) -> bool {
+    let types_ok = types.map_or(true, |types| types.iter().all(|t| tags.contains(&t.as_str())));
+    let types_or_ok = types_or.map_or(true, |types| types.iter().any(|t| tags.contains(&t.as_str())));
+    let exclude_types_ok = exclude_types.map_or(true, |types| !types.iter().any(|t| tags.contains(&t.as_str())));
+    types_ok && types_or_ok && exclude_types_ok
+}
+
+/// Coarse per-extension type tags for `path`, standing in for the full
+/// `identify`-style classification pre-commit normally matches `types`
+/// against. Every file is at least `"file"` and `"text"`.
+fn file_types(path: &str) -> Vec<&'static str> {
+    let mut tags = vec!["file", "text"];
+    match std::path::Path::new(path).extension().and_then(|ext| ext.to_str()) {
+        Some("rs") => tags.push("rust"),
+        Some("go") => tags.push("go"),
+        Some("py") => tags.push("python"),
+        Some("rb") => tags.push("ruby"),
+        Some("js") => tags.push("javascript"),
+        Some("ts") => tags.push("typescript"),
+        Some("json") => tags.push("json"),
+        Some("yaml" | "yml") => tags.push("yaml"),
+        Some("toml") => tags.push("toml"),
+        Some("md") => tags.push("markdown"),
+        Some("sh") => tags.push("shell"),
+        _ => {}
+    }
+    tags
+}
+
+fn repo_files() -> Vec<String> {
+    ignore::WalkBuilder::new(CWD.as_path())
+        .hidden(false)
+        .build()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_some_and(|ft| ft.is_file()))
+        .filter_map(|entry| {
+            entry
+                .path()
+                .strip_prefix(CWD.as_path())
+                .ok()
+                .map(|path| path.to_string_lossy().replace('\\', "/"))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn file_types_tags_by_extension() {
+        assert_eq!(file_types("src/main.rs"), vec!["file", "text", "rust"]);
+        assert_eq!(file_types("README"), vec!["file", "text"]);
+    }
+
+    #[test]
+    fn types_requires_all_tags_present() {
+        let types = ["file".to_string(), "rust".to_string()];
+        assert!(matches_type_filters(Some(&types), None, None, &["file", "text", "rust"]));
+        assert!(!matches_type_filters(Some(&types), None, None, &["file", "text"]));
+    }
+
+    #[test]
+    fn types_or_requires_any_tag_present() {
+        let types_or = ["python".to_string(), "rust".to_string()];
+        assert!(matches_type_filters(None, Some(&types_or), None, &["file", "text", "rust"]));
+        assert!(!matches_type_filters(None, Some(&types_or), None, &["file", "text", "go"]));
+    }
+
+    #[test]
+    fn exclude_types_rejects_any_tag_present() {
+        let exclude_types = ["markdown".to_string()];
+        assert!(matches_type_filters(None, None, Some(&exclude_types), &["file", "text", "rust"]));
+        assert!(!matches_type_filters(None, None, Some(&exclude_types), &["file", "text", "markdown"]));
+    }
+
+    #[test]
+    fn absent_filters_match_everything() {
+        assert!(matches_type_filters(None, None, None, &["file", "text"]));
+    }
+}
@@ -6,9 +6,13 @@ use anyhow::Result;
 use crate::config;
 use crate::hook::Hook;
 
+mod golang;
+pub(crate) mod meta;
 mod node;
 mod python;
+mod rust;
 mod system;
+mod wasm;
 
 pub const DEFAULT_VERSION: &str = "default";
 
@@ -21,11 +25,15 @@ trait LanguageImpl {
     async fn run(&self, hook: &Hook, filenames: &[&String]) -> Result<Output>;
 }
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum Language {
     Python(python::Python),
     Node(node::Node),
     System(system::System),
+    Meta(meta::Meta),
+    Rust(rust::Rust),
+    Golang(golang::Golang),
+    Wasm(wasm::WasmLanguage),
 }
 
 impl From<config::Language> for Language {
@@ -38,7 +46,6 @@ impl From<config::Language> for Language {
             // config::Language::DockerImage => Language::DockerImage,
             // config::Language::Dotnet => Language::Dotnet,
             // config::Language::Fail => Language::Fail,
-            // config::Language::Golang => Language::Golang,
             // config::Language::Haskell => Language::Haskell,
             // config::Language::Lua => Language::Lua,
             config::Language::Node => Language::Node(node::Node),
@@ -46,12 +53,17 @@ impl From<config::Language> for Language {
             config::Language::Python => Language::Python(python::Python),
             // config::Language::R => Language::R,
             // config::Language::Ruby => Language::Ruby,
-            // config::Language::Rust => Language::Rust,
             // config::Language::Swift => Language::Swift,
             // config::Language::Pygrep => Language::Pygrep,
             // config::Language::Script => Language::Script,
             config::Language::System => Language::System(system::System),
-            _ => todo!("Not implemented yet"),
+            config::Language::Meta => Language::Meta(meta::Meta),
+            config::Language::Rust => Language::Rust(rust::Rust),
+            config::Language::Golang => Language::Golang(golang::Golang),
+            other => match wasm::WasmLanguage::discover(other, &wasm::search_dirs()) {
+                Some(plugin) => Language::Wasm(plugin),
+                None => todo!("Not implemented yet"),
+            },
         }
     }
 }
@@ -62,6 +74,10 @@ impl Display for Language {
             Self::Python(python) => python.fmt(f),
             Self::Node(node) => node.fmt(f),
             Self::System(system) => system.fmt(f),
+            Self::Meta(meta) => meta.fmt(f),
+            Self::Rust(rust) => rust.fmt(f),
+            Self::Golang(golang) => golang.fmt(f),
+            Self::Wasm(wasm) => wasm.fmt(f),
         }
     }
 }
@@ -72,6 +88,10 @@ impl Language {
             Self::Python(python) => python.name(),
             Self::Node(node) => node.name(),
             Self::System(system) => system.name(),
+            Self::Meta(meta) => meta.name(),
+            Self::Rust(rust) => rust.name(),
+            Self::Golang(golang) => golang.name(),
+            Self::Wasm(wasm) => wasm.config_language(),
         }
     }
 
@@ -80,6 +100,10 @@ impl Language {
             Self::Python(python) => python.default_version(),
             Self::Node(node) => node.default_version(),
             Self::System(system) => system.default_version(),
+            Self::Meta(meta) => meta.default_version(),
+            Self::Rust(rust) => rust.default_version(),
+            Self::Golang(golang) => golang.default_version(),
+            Self::Wasm(wasm) => wasm.default_version(),
         }
     }
 
@@ -88,6 +112,10 @@ impl Language {
             Self::Python(python) => python.environment_dir(),
             Self::Node(node) => node.environment_dir(),
             Self::System(system) => system.environment_dir(),
+            Self::Meta(meta) => meta.environment_dir(),
+            Self::Rust(rust) => rust.environment_dir(),
+            Self::Golang(golang) => golang.environment_dir(),
+            Self::Wasm(wasm) => wasm.environment_dir(),
         }
     }
 
@@ -96,6 +124,10 @@ impl Language {
             Self::Python(python) => python.install(hook).await,
             Self::Node(node) => node.install(hook).await,
             Self::System(system) => system.install(hook).await,
+            Self::Meta(meta) => meta.install(hook).await,
+            Self::Rust(rust) => rust.install(hook).await,
+            Self::Golang(golang) => golang.install(hook).await,
+            Self::Wasm(wasm) => wasm.install(hook).await,
         }
     }
 
@@ -104,6 +136,10 @@ impl Language {
             Self::Python(python) => python.check_health().await,
             Self::Node(node) => node.check_health().await,
             Self::System(system) => system.check_health().await,
+            Self::Meta(meta) => meta.check_health().await,
+            Self::Rust(rust) => rust.check_health().await,
+            Self::Golang(golang) => golang.check_health().await,
+            Self::Wasm(wasm) => wasm.check_health().await,
         }
     }
 
@@ -112,6 +148,10 @@ impl Language {
             Self::Python(python) => python.run(hook, filenames).await,
             Self::Node(node) => node.run(hook, filenames).await,
             Self::System(system) => system.run(hook, filenames).await,
+            Self::Meta(meta) => meta.run(hook, filenames).await,
+            Self::Rust(rust) => rust.run(hook, filenames).await,
+            Self::Golang(golang) => golang.run(hook, filenames).await,
+            Self::Wasm(wasm) => wasm.run(hook, filenames).await,
         }
     }
 }
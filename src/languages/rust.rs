@@ -0,0 +1,178 @@
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+
+use anyhow::{ensure, Context, Result};
+use tokio::process::Command;
+
+use crate::config;
+use crate::hook::Hook;
+use crate::run::run_by_batch;
+
+use super::DEFAULT_VERSION;
+
+#[derive(Debug, Copy, Clone)]
+pub struct Rust;
+
+impl Display for Rust {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "rust")
+    }
+}
+
+impl Rust {
+    pub fn name(self) -> config::Language {
+        config::Language::Rust
+    }
+
+    pub fn default_version(&self) -> &str {
+        DEFAULT_VERSION
+    }
+
+    pub fn environment_dir(&self) -> Option<&str> {
+        Some("rustenv")
+    }
+
+    fn env_dir(&self, hook: &Hook) -> PathBuf {
+        let version = hook.language_version.as_deref().unwrap_or(DEFAULT_VERSION);
+        env_dir_path(hook.path(), self.environment_dir().unwrap(), version)
+    }
+
+    async fn ensure_toolchain(&self, version: &str) -> Result<()> {
+        if version == DEFAULT_VERSION {
+            return Ok(());
+        }
+
+        let status = Command::new("rustup")
+            .args(["toolchain", "install", version, "--profile", "minimal"])
+            .status()
+            .await
+            .context("failed to execute `rustup`")?;
+        ensure!(status.success(), "failed to install Rust toolchain `{version}`");
+        Ok(())
+    }
+
+    pub async fn install(&self, hook: &Hook) -> Result<()> {
+        let env_dir = self.env_dir(hook);
+        let version = hook.language_version.as_deref().unwrap_or(DEFAULT_VERSION);
+        self.ensure_toolchain(version).await?;
+
+        let mut cmd = Command::new("cargo");
+        if version != DEFAULT_VERSION {
+            cmd.arg(format!("+{version}"));
+        }
+        let status = cmd
+            .args(["install", "--root"])
+            .arg(&env_dir)
+            .args(["--path", "."])
+            .current_dir(hook.path())
+            .status()
+            .await
+            .context("failed to execute `cargo install`")?;
+        ensure!(status.success(), "failed to build hook repo with `cargo install`");
+
+        for dep in hook.additional_dependencies.iter().flatten() {
+            let mut cmd = Command::new("cargo");
+            if version != DEFAULT_VERSION {
+                cmd.arg(format!("+{version}"));
+            }
+            let status = cmd
+                .args(["install", "--root"])
+                .arg(&env_dir)
+                .arg(dep)
+                .current_dir(hook.path())
+                .status()
+                .await
+                .context("failed to execute `cargo install`")?;
+            ensure!(status.success(), "failed to install additional dependency `{dep}`");
+        }
+
+        Ok(())
+    }
+
+    pub async fn check_health(&self) -> Result<()> {
+        let status = Command::new("cargo")
+            .arg("--version")
+            .status()
+            .await
+            .context("failed to execute `cargo`")?;
+        ensure!(status.success(), "`cargo` is not available on PATH");
+        Ok(())
+    }
+
+    pub async fn run(&self, hook: &Hook, filenames: &[&String]) -> Result<Output> {
+        let env_dir = self.env_dir(hook);
+        let bin_dir = env_dir.join("bin");
+        let path = std::env::join_paths(std::iter::once(bin_dir).chain(std::env::split_paths(
+            &std::env::var_os("PATH").unwrap_or_default(),
+        )))?;
+
+        let entry = shlex::split(&hook.entry).ok_or_else(|| anyhow::anyhow!("failed to parse entry"))?;
+        let repo_dir = hook.path().to_path_buf();
+
+        let run = async move |batch: Vec<String>| {
+            let mut cmd = Command::new(&entry[0]);
+            let output = cmd
+                .args(&entry[1..])
+                .args(&hook.args)
+                .args(batch)
+                .env("PATH", &path)
+                .current_dir(&repo_dir)
+                .output()
+                .await?;
+
+            let mut combined = output.stdout;
+            combined.extend(output.stderr);
+            anyhow::Ok((output.status.code().unwrap_or(1), combined))
+        };
+
+        let results = run_by_batch(hook, filenames, run).await?;
+
+        let mut combined_status = 0;
+        let mut combined_output = Vec::new();
+        for (code, output) in results {
+            combined_status |= code;
+            combined_output.extend(output);
+        }
+
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(combined_status << 8);
+        #[cfg(not(unix))]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(combined_status as u32);
+
+        Ok(Output {
+            status,
+            stdout: combined_output,
+            stderr: Vec::new(),
+        })
+    }
+}
+
+/// The per-version toolchain environment directory under the hook's own repo
+/// checkout (`repo_dir`), e.g. `<repo_dir>/rustenv-1.75`. Pulled out as a pure
+/// function so it's testable without a [`Hook`], which needs a full manifest
+/// to construct.
+fn env_dir_path(repo_dir: &Path, env_dir_name: &str, version: &str) -> PathBuf {
+    repo_dir.join(format!("{env_dir_name}-{version}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn env_dir_path_is_anchored_under_the_repo_checkout() {
+        assert_eq!(
+            env_dir_path(Path::new("/store/repos/abc123"), "rustenv", "1.75"),
+            PathBuf::from("/store/repos/abc123/rustenv-1.75")
+        );
+    }
+
+    #[test]
+    fn env_dir_path_includes_the_version_in_the_directory_name() {
+        assert_eq!(
+            env_dir_path(Path::new("/store/repos/abc123"), "rustenv", DEFAULT_VERSION),
+            PathBuf::from(format!("/store/repos/abc123/rustenv-{DEFAULT_VERSION}"))
+        );
+    }
+}
@@ -0,0 +1,238 @@
+use std::path::{Component, Path, PathBuf};
+
+use anyhow::{bail, Result};
+use tokio::process::Command;
+use wasmtime_wasi::{WasiCtx, WasiCtxBuilder, WasiView};
+
+use super::bindings::pre_commit::language_plugin::host::Host;
+
+/// Per-call state handed to a guest instance. Bounds every filesystem and
+/// process operation the guest can perform to `repo_dir` (the hook's repo
+/// checkout) and `env_dir` (the plugin's own environment under the store);
+/// nothing else is reachable, and no ambient `PATH`/shell is exposed beyond
+/// what `spawn` explicitly passes through.
+pub struct HostState {
+    wasi: WasiCtx,
+    table: wasmtime::component::ResourceTable,
+    repo_dir: PathBuf,
+    env_dir: PathBuf,
+}
+
+impl HostState {
+    pub fn new(repo_dir: PathBuf, env_dir: PathBuf) -> Self {
+        Self {
+            wasi: WasiCtxBuilder::new().build(),
+            table: wasmtime::component::ResourceTable::new(),
+            repo_dir,
+            env_dir,
+        }
+    }
+
+    /// Resolve `path` against the sandbox roots, rejecting anything that
+    /// escapes both the repo checkout and the environment directory.
+    ///
+    /// `path` may not exist yet (`write_file` creates new files), so this
+    /// can't just `canonicalize` the full join and compare prefixes. Instead
+    /// `resolve_within` canonicalizes only the deepest *existing* ancestor —
+    /// resolving any symlinks on the way — checks that against the
+    /// canonicalized root, then appends the remaining (necessarily
+    /// not-yet-existing, so not-a-symlink) path components lexically. A
+    /// purely lexical check on the un-normalized join is not enough:
+    /// `root.join("../../etc/passwd")` still starts with `root` as a path,
+    /// even though it resolves outside it; and a lexical check alone also
+    /// misses a symlink *inside* the root that itself points outside it.
+    fn sandbox_path(&self, path: &str) -> Result<PathBuf> {
+        for root in [&self.repo_dir, &self.env_dir] {
+            if let Some(candidate) = resolve_within(root, path) {
+                return Ok(candidate);
+            }
+        }
+        bail!("path `{path}` escapes the plugin sandbox")
+    }
+}
+
+/// Resolve `root.join(path)`, following symlinks through every *existing*
+/// ancestor directory, and return it only if it stays within `root` once
+/// those symlinks are followed. Returns `None` if it escapes `root`, or if
+/// `root` itself doesn't exist.
+fn resolve_within(root: &Path, path: &str) -> Option<PathBuf> {
+    let root = root.canonicalize().ok()?;
+    let joined = normalize(&root.join(path));
+
+    // Walk up from the full (lexically normalized) join to the deepest
+    // ancestor that actually exists on disk, so `canonicalize` has
+    // something to resolve; the remaining, non-existent tail can't itself
+    // be a symlink, so it's safe to reattach without re-resolving it.
+    let mut existing = joined.as_path();
+    let mut tail = Vec::new();
+    while !existing.exists() {
+        tail.push(existing.file_name()?.to_os_string());
+        existing = existing.parent()?;
+    }
+
+    let mut resolved = existing.canonicalize().ok()?;
+    if !resolved.starts_with(&root) {
+        return None;
+    }
+    for component in tail.into_iter().rev() {
+        resolved.push(component);
+    }
+    resolved.starts_with(&root).then_some(resolved)
+}
+
+/// Lexically collapse `.`/`..` components without touching the filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::ParentDir => {
+                result.pop();
+            }
+            Component::CurDir => {}
+            other => result.push(other),
+        }
+    }
+    result
+}
+
+impl WasiView for HostState {
+    fn ctx(&mut self) -> &mut WasiCtx {
+        &mut self.wasi
+    }
+
+    fn table(&mut self) -> &mut wasmtime::component::ResourceTable {
+        &mut self.table
+    }
+}
+
+impl Host for HostState {
+    async fn spawn(
+        &mut self,
+        program: String,
+        args: Vec<String>,
+        cwd: String,
+        env: Vec<(String, String)>,
+    ) -> wasmtime::Result<Result<(i32, Vec<u8>), String>> {
+        let cwd = match self.sandbox_path(&cwd) {
+            Ok(cwd) => cwd,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+
+        let output = Command::new(&program)
+            .args(&args)
+            .current_dir(&cwd)
+            .env_clear()
+            .envs(env)
+            .output()
+            .await;
+
+        let output = match output {
+            Ok(output) => output,
+            Err(err) => return Ok(Err(format!("failed to spawn `{program}`: {err}"))),
+        };
+
+        let mut bytes = output.stdout;
+        bytes.extend(output.stderr);
+        Ok(Ok((output.status.code().unwrap_or(1), bytes)))
+    }
+
+    async fn read_file(&mut self, path: String) -> wasmtime::Result<Result<Vec<u8>, String>> {
+        let path = match self.sandbox_path(&path) {
+            Ok(path) => path,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+        Ok(std::fs::read(&path).map_err(|err| err.to_string()))
+    }
+
+    async fn write_file(&mut self, path: String, contents: Vec<u8>) -> wasmtime::Result<Result<(), String>> {
+        let path = match self.sandbox_path(&path) {
+            Ok(path) => path,
+            Err(err) => return Ok(Err(err.to_string())),
+        };
+        if let Some(parent) = path.parent() {
+            if let Err(err) = std::fs::create_dir_all(parent) {
+                return Ok(Err(err.to_string()));
+            }
+        }
+        Ok(std::fs::write(&path, contents).map_err(|err| err.to_string()))
+    }
+
+    async fn get_env(&mut self, name: String) -> wasmtime::Result<Option<String>> {
+        Ok(std::env::var(name).ok())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    use super::*;
+
+    /// A scratch directory under the system temp dir, removed on drop, so
+    /// each test gets its own on-disk sandbox root without a tempfile dep.
+    struct ScratchDir(PathBuf);
+
+    impl ScratchDir {
+        fn new(name: &str) -> Self {
+            static COUNTER: AtomicU32 = AtomicU32::new(0);
+            let path = std::env::temp_dir().join(format!(
+                "pre-commit-wasm-host-test-{name}-{}-{}",
+                std::process::id(),
+                COUNTER.fetch_add(1, Ordering::Relaxed)
+            ));
+            std::fs::create_dir_all(&path).unwrap();
+            Self(path)
+        }
+    }
+
+    impl Drop for ScratchDir {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn normalize_collapses_dot_and_dot_dot() {
+        assert_eq!(normalize(Path::new("/root/./child/../sibling")), PathBuf::from("/root/sibling"));
+    }
+
+    #[test]
+    fn resolve_within_allows_a_plain_path_under_root() {
+        let root = ScratchDir::new("plain");
+        std::fs::write(root.0.join("file.txt"), b"hi").unwrap();
+        assert_eq!(resolve_within(&root.0, "file.txt"), Some(root.0.join("file.txt")));
+    }
+
+    #[test]
+    fn resolve_within_allows_a_not_yet_existing_path_under_root() {
+        let root = ScratchDir::new("new-file");
+        assert_eq!(resolve_within(&root.0, "new/file.txt"), Some(root.0.join("new/file.txt")));
+    }
+
+    #[test]
+    fn resolve_within_rejects_lexical_escape() {
+        let root = ScratchDir::new("lexical-escape");
+        assert_eq!(resolve_within(&root.0, "../../etc/passwd"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_within_rejects_a_symlink_that_escapes_root() {
+        let root = ScratchDir::new("symlink-escape");
+        let outside = ScratchDir::new("symlink-escape-target");
+        std::fs::write(outside.0.join("secret.txt"), b"secret").unwrap();
+        std::os::unix::fs::symlink(&outside.0, root.0.join("link")).unwrap();
+        assert_eq!(resolve_within(&root.0, "link/secret.txt"), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_within_allows_a_symlink_that_stays_inside_root() {
+        let root = ScratchDir::new("symlink-inside");
+        std::fs::create_dir(root.0.join("real")).unwrap();
+        std::fs::write(root.0.join("real/file.txt"), b"hi").unwrap();
+        std::os::unix::fs::symlink(root.0.join("real"), root.0.join("link")).unwrap();
+        let resolved = resolve_within(&root.0, "link/file.txt").unwrap();
+        assert_eq!(resolved, root.0.join("real/file.txt"));
+    }
+}
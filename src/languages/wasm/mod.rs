@@ -0,0 +1,206 @@
+use std::fmt::{self, Display};
+use std::path::{Path, PathBuf};
+use std::process::Output;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use wasmtime::component::Component;
+use wasmtime::{Config, Engine, Store as WasmtimeStore};
+
+use crate::config;
+use crate::hook::Hook;
+
+use super::DEFAULT_VERSION;
+use host::HostState;
+
+mod host;
+
+wasmtime::component::bindgen!({
+    path: "src/languages/wasm/plugin.wit",
+    world: "language-plugin",
+    async: true,
+});
+
+/// A language backend implemented as a `.wasm` component, discovered in the
+/// store's plugin directory. Each instance owns a compiled `Component` and
+/// knows the environment directory name the guest reported for itself.
+#[derive(Clone)]
+pub struct WasmLanguage {
+    name: String,
+    config_language: config::Language,
+    engine: Engine,
+    component: Arc<Component>,
+    environment_dir: Option<String>,
+    default_version: String,
+}
+
+impl fmt::Debug for WasmLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WasmLanguage").field("name", &self.name).finish()
+    }
+}
+
+impl Display for WasmLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "wasm:{}", self.name)
+    }
+}
+
+/// Directories searched for `<name>.wasm` plugins: the store's own
+/// `plugins` directory (requires `Store::plugins_dir`, added alongside the
+/// rest of the store's on-disk layout), plus any extra directories the user
+/// lists in `PRE_COMMIT_WASM_PLUGINS` (platform path-list separated).
+pub(super) fn search_dirs() -> Vec<PathBuf> {
+    let mut dirs = vec![crate::store::Store::plugins_dir()];
+    if let Some(extra) = std::env::var_os("PRE_COMMIT_WASM_PLUGINS") {
+        dirs.extend(std::env::split_paths(&extra));
+    }
+    dirs
+}
+
+impl WasmLanguage {
+    /// Load and instantiate the plugin once, reading its static metadata
+    /// (`default-version`, `environment-dir`) up front so they're cheap to
+    /// query afterwards without re-entering the guest.
+    fn load(config_language: config::Language, name: &str, module_path: &Path) -> Result<Self> {
+        let mut config = Config::new();
+        config.async_support(true);
+        config.wasm_component_model(true);
+        let engine = Engine::new(&config).context("failed to create wasmtime engine")?;
+        let component = Component::from_file(&engine, module_path)
+            .with_context(|| format!("failed to load wasm plugin `{}`", module_path.display()))?;
+
+        // A throwaway store/instance purely to ask the guest for its static
+        // metadata; `install`/`run` create their own stores scoped to the
+        // hook's sandbox roots.
+        let metadata_dir = module_path
+            .parent()
+            .map(Path::to_path_buf)
+            .unwrap_or_else(|| PathBuf::from("."));
+        let (default_version, environment_dir) = futures::executor::block_on(async {
+            let mut linker = wasmtime::component::Linker::new(&engine);
+            wasmtime_wasi::add_to_linker_async(&mut linker)?;
+            LanguagePlugin::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+
+            let state = HostState::new(metadata_dir.clone(), metadata_dir);
+            let mut store = WasmtimeStore::new(&engine, state);
+            let plugin = LanguagePlugin::instantiate_async(&mut store, &component, &linker).await?;
+
+            let default_version = plugin.call_default_version(&mut store).await?;
+            let environment_dir = plugin.call_environment_dir(&mut store).await?;
+            anyhow::Ok((default_version, environment_dir))
+        })?;
+
+        Ok(Self {
+            name: name.to_string(),
+            config_language,
+            engine,
+            component: Arc::new(component),
+            environment_dir,
+            default_version,
+        })
+    }
+
+    /// Look up a registered plugin for `config_language` by its config name
+    /// (e.g. `golang`, `ruby`), checking the store's plugin directory
+    /// followed by any user-configured plugin directories. Returns `None`
+    /// if no plugin claims the name, letting the caller fall through to
+    /// `todo!()` for genuinely unimplemented languages.
+    pub fn discover(config_language: config::Language, search_dirs: &[PathBuf]) -> Option<Self> {
+        let name = config_language.to_string();
+        search_dirs.iter().find_map(|dir| {
+            let candidate = dir.join(format!("{name}.wasm"));
+            candidate
+                .is_file()
+                .then(|| Self::load(config_language, &name, &candidate))
+                .and_then(Result::ok)
+        })
+    }
+
+    pub fn config_language(&self) -> config::Language {
+        self.config_language
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn default_version(&self) -> &str {
+        if self.default_version.is_empty() {
+            DEFAULT_VERSION
+        } else {
+            &self.default_version
+        }
+    }
+
+    pub fn environment_dir(&self) -> Option<&str> {
+        self.environment_dir.as_deref()
+    }
+
+    async fn instantiate(&self, hook: &Hook) -> Result<(WasmtimeStore<HostState>, LanguagePlugin)> {
+        let mut linker = wasmtime::component::Linker::new(&self.engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+        LanguagePlugin::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+
+        let repo_dir = hook.path().to_path_buf();
+        let env_dir = match &self.environment_dir {
+            Some(name) => repo_dir.join(name),
+            None => repo_dir.clone(),
+        };
+        let state = HostState::new(repo_dir, env_dir);
+        let mut store = WasmtimeStore::new(&self.engine, state);
+        let plugin = LanguagePlugin::instantiate_async(&mut store, &self.component, &linker).await?;
+        Ok((store, plugin))
+    }
+
+    pub async fn install(&self, hook: &Hook) -> Result<()> {
+        let (mut store, plugin) = self.instantiate(hook).await?;
+        let hook_json = serde_json::to_string(hook)?;
+        plugin
+            .call_install(&mut store, &hook_json)
+            .await?
+            .map_err(|err| anyhow::anyhow!("plugin `{}` failed to install: {err}", self.name))
+    }
+
+    pub async fn check_health(&self) -> Result<()> {
+        let mut linker = wasmtime::component::Linker::new(&self.engine);
+        wasmtime_wasi::add_to_linker_async(&mut linker)?;
+        LanguagePlugin::add_to_linker(&mut linker, |state: &mut HostState| state)?;
+
+        // No hook is running yet, so there's no repo checkout to sandbox to;
+        // scope the guest to a directory that's at least specific to this
+        // plugin instead of handing it the whole process cwd.
+        let health_dir = std::env::temp_dir().join(format!("pre-commit-wasm-health-{}", self.name));
+        let state = HostState::new(health_dir.clone(), health_dir);
+        let mut store = WasmtimeStore::new(&self.engine, state);
+        let plugin = LanguagePlugin::instantiate_async(&mut store, &self.component, &linker).await?;
+        plugin
+            .call_check_health(&mut store)
+            .await?
+            .map_err(|err| anyhow::anyhow!("plugin `{}` is unhealthy: {err}", self.name))
+    }
+
+    pub async fn run(&self, hook: &Hook, filenames: &[&String]) -> Result<Output> {
+        let (mut store, plugin) = self.instantiate(hook).await?;
+        let hook_json = serde_json::to_string(hook)?;
+        let filenames = filenames.iter().map(|f| f.to_string()).collect::<Vec<_>>();
+
+        let (exit_code, output_bytes) = plugin
+            .call_run(&mut store, &hook_json, &filenames)
+            .await?
+            .map_err(|err| anyhow::anyhow!("plugin `{}` failed to run: {err}", self.name))?;
+
+        // The guest only reports an exit code, not a full `wait(2)` status;
+        // encode it the way a normally-exited child process would be.
+        #[cfg(unix)]
+        let status = std::os::unix::process::ExitStatusExt::from_raw(exit_code << 8);
+        #[cfg(not(unix))]
+        let status = std::os::windows::process::ExitStatusExt::from_raw(exit_code as u32);
+
+        Ok(Output {
+            status,
+            stdout: output_bytes,
+            stderr: Vec::new(),
+        })
+    }
+}
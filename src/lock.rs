@@ -0,0 +1,157 @@
+use std::path::Path;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+pub const LOCK_FILE: &str = ".pre-commit-lock";
+
+/// Snapshot of every remote repo's resolved commit id and each hook's
+/// `additional_dependencies` as configured, written after a successful
+/// `Project::hooks` resolution so a second machine (or a second run here)
+/// checks out the same commit instead of re-resolving `rev` against
+/// whatever is current upstream. Dependency specs are stored as written,
+/// not as the versions an install resolved them to.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct Lockfile {
+    repo: Vec<LockedRepo>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockedRepo {
+    url: String,
+    rev: String,
+    commit: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    hooks: Vec<LockedHook>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct LockedHook {
+    id: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    additional_dependencies: Vec<String>,
+}
+
+impl Lockfile {
+    pub fn read(root: &Path) -> Result<Option<Self>> {
+        let path = root.join(LOCK_FILE);
+        if !path.is_file() {
+            return Ok(None);
+        }
+
+        let content = std::fs::read_to_string(&path).with_context(|| format!("failed to read `{}`", path.display()))?;
+        Ok(Some(
+            toml::from_str(&content).with_context(|| format!("failed to parse `{}`", path.display()))?,
+        ))
+    }
+
+    pub fn write(&self, root: &Path) -> Result<()> {
+        let path = root.join(LOCK_FILE);
+        let content = toml::to_string_pretty(self).context("failed to serialize lockfile")?;
+        std::fs::write(&path, &content).with_context(|| format!("failed to write `{}`", path.display()))
+    }
+
+    /// The commit `url`@`rev` was pinned to the last time the lockfile was
+    /// written, if any. Once `rev` itself changes in the config, the old
+    /// entry simply won't match and this returns `None`.
+    pub fn locked_commit(&self, url: &str, rev: &str) -> Option<&str> {
+        self.repo
+            .iter()
+            .find(|repo| repo.url == url && repo.rev == rev)
+            .map(|repo| repo.commit.as_str())
+    }
+
+    /// The `additional_dependencies` recorded for hook `hook_id` of
+    /// `url`@`rev` the last time the lockfile was written, if any.
+    pub fn locked_dependencies(&self, url: &str, rev: &str, hook_id: &str) -> Option<&[String]> {
+        self.repo
+            .iter()
+            .find(|repo| repo.url == url && repo.rev == rev)
+            .and_then(|repo| repo.hooks.iter().find(|hook| hook.id == hook_id))
+            .map(|hook| hook.additional_dependencies.as_slice())
+    }
+
+    fn find_or_insert_repo(&mut self, url: &str, rev: &str) -> &mut LockedRepo {
+        if let Some(index) = self.repo.iter().position(|repo| repo.url == url && repo.rev == rev) {
+            return &mut self.repo[index];
+        }
+
+        self.repo.push(LockedRepo {
+            url: url.to_string(),
+            rev: rev.to_string(),
+            commit: String::new(),
+            hooks: Vec::new(),
+        });
+        self.repo.last_mut().expect("just pushed")
+    }
+
+    /// Pin `url`@`rev` to the concrete `commit` it was just resolved to.
+    pub fn pin_repo(&mut self, url: &str, rev: &str, commit: &str) {
+        self.find_or_insert_repo(url, rev).commit = commit.to_string();
+    }
+
+    /// Record the `additional_dependencies` specs a hook of `url`@`rev` is
+    /// configured with. The specs are stored verbatim; an unpinned spec is
+    /// resolved again whenever it is installed.
+    pub fn record_hook(&mut self, url: &str, rev: &str, additional_dependencies: &[String], hook_id: &str) {
+        let repo = self.find_or_insert_repo(url, rev);
+        match repo.hooks.iter_mut().find(|hook| hook.id == hook_id) {
+            Some(hook) => hook.additional_dependencies = additional_dependencies.to_vec(),
+            None => repo.hooks.push(LockedHook {
+                id: hook_id.to_string(),
+                additional_dependencies: additional_dependencies.to_vec(),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locked_commit_round_trips_by_url_and_rev() {
+        let mut lockfile = Lockfile::default();
+        lockfile.pin_repo("https://example.com/repo", "v1", "abc123");
+        assert_eq!(lockfile.locked_commit("https://example.com/repo", "v1"), Some("abc123"));
+    }
+
+    #[test]
+    fn locked_commit_misses_once_rev_changes() {
+        let mut lockfile = Lockfile::default();
+        lockfile.pin_repo("https://example.com/repo", "v1", "abc123");
+        assert_eq!(lockfile.locked_commit("https://example.com/repo", "v2"), None);
+    }
+
+    #[test]
+    fn pin_repo_updates_an_existing_entry_in_place() {
+        let mut lockfile = Lockfile::default();
+        lockfile.pin_repo("https://example.com/repo", "v1", "abc123");
+        lockfile.pin_repo("https://example.com/repo", "v1", "def456");
+        assert_eq!(lockfile.locked_commit("https://example.com/repo", "v1"), Some("def456"));
+        assert_eq!(lockfile.repo.len(), 1);
+    }
+
+    #[test]
+    fn locked_dependencies_round_trips_by_hook_id() {
+        let mut lockfile = Lockfile::default();
+        let deps = vec!["requests==2.31.0".to_string()];
+        lockfile.record_hook("https://example.com/repo", "v1", &deps, "my-hook");
+        assert_eq!(
+            lockfile.locked_dependencies("https://example.com/repo", "v1", "my-hook"),
+            Some(deps.as_slice())
+        );
+        assert_eq!(lockfile.locked_dependencies("https://example.com/repo", "v1", "other-hook"), None);
+    }
+
+    #[test]
+    fn record_hook_overwrites_previous_dependencies_for_same_hook() {
+        let mut lockfile = Lockfile::default();
+        lockfile.record_hook("https://example.com/repo", "v1", &["a".to_string()], "my-hook");
+        lockfile.record_hook("https://example.com/repo", "v1", &["b".to_string()], "my-hook");
+        assert_eq!(
+            lockfile.locked_dependencies("https://example.com/repo", "v1", "my-hook"),
+            Some(["b".to_string()].as_slice())
+        );
+    }
+}
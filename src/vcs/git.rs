@@ -0,0 +1,70 @@
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+use tokio::process::Command;
+use url::Url;
+
+use super::Backend;
+
+#[derive(Debug, Copy, Clone)]
+pub struct GitBackend;
+
+impl GitBackend {
+    async fn git(args: &[&str], dir: Option<&Path>) -> Result<String> {
+        let mut cmd = Command::new("git");
+        cmd.args(args);
+        if let Some(dir) = dir {
+            cmd.current_dir(dir);
+        }
+        let output = cmd.output().await.context("failed to execute `git`")?;
+        ensure!(
+            output.status.success(),
+            "`git {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+impl Backend for GitBackend {
+    fn name(&self) -> &'static str {
+        "git"
+    }
+
+    fn supports(&self, url: &Url) -> bool {
+        // Git is first in the registry and claims the common network and
+        // local-file schemes.
+        matches!(url.scheme(), "http" | "https" | "ssh" | "git" | "file")
+    }
+
+    async fn clone(&self, url: &Url, rev: &str, dest: &Path) -> Result<()> {
+        Self::git(
+            &["clone", "--quiet", "--no-checkout", url.as_str(), &dest.to_string_lossy()],
+            None,
+        )
+        .await?;
+        self.checkout(dest, rev).await
+    }
+
+    async fn fetch(&self, dest: &Path) -> Result<()> {
+        Self::git(&["fetch", "--quiet", "--tags"], Some(dest)).await.map(drop)
+    }
+
+    async fn checkout(&self, dest: &Path, rev: &str) -> Result<()> {
+        Self::git(&["checkout", "--quiet", rev], Some(dest)).await.map(drop)
+    }
+
+    /// Looks `rev` up among the remote's ref names with `git ls-remote`, so
+    /// only branch and tag names resolve; a commit hash (short or full) is
+    /// reported as not found.
+    async fn resolve_rev(&self, url: &Url, rev: &str) -> Result<String> {
+        let output = Self::git(&["ls-remote", "--exit-code", url.as_str(), rev], None).await?;
+        output
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().next())
+            .map(str::to_string)
+            .ok_or_else(|| anyhow::anyhow!("`{rev}` not found in `{url}`"))
+    }
+}
@@ -0,0 +1,63 @@
+use std::path::Path;
+
+use anyhow::{ensure, Context, Result};
+use tokio::process::Command;
+use url::Url;
+
+use super::Backend;
+
+#[derive(Debug, Copy, Clone)]
+pub struct MercurialBackend;
+
+impl MercurialBackend {
+    async fn hg(args: &[&str], dir: Option<&Path>) -> Result<String> {
+        let mut cmd = Command::new("hg");
+        cmd.args(args);
+        if let Some(dir) = dir {
+            cmd.current_dir(dir);
+        }
+        let output = cmd.output().await.context("failed to execute `hg`")?;
+        ensure!(
+            output.status.success(),
+            "`hg {}` failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr)
+        );
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+    }
+}
+
+impl Backend for MercurialBackend {
+    fn name(&self) -> &'static str {
+        "hg"
+    }
+
+    fn supports(&self, url: &Url) -> bool {
+        // Only the `hg://` scheme is claimed here; Mercurial repos served
+        // over `http(s)://` or `ssh://` need an explicit `repo_type: hg`.
+        url.scheme() == "hg"
+    }
+
+    async fn clone(&self, url: &Url, rev: &str, dest: &Path) -> Result<()> {
+        Self::hg(
+            &["clone", "--quiet", "--updaterev", rev, url.as_str(), &dest.to_string_lossy()],
+            None,
+        )
+        .await
+        .map(drop)
+    }
+
+    async fn fetch(&self, dest: &Path) -> Result<()> {
+        Self::hg(&["pull", "--quiet"], Some(dest)).await.map(drop)
+    }
+
+    async fn checkout(&self, dest: &Path, rev: &str) -> Result<()> {
+        Self::hg(&["update", "--quiet", "--rev", rev], Some(dest)).await.map(drop)
+    }
+
+    /// Returns the short (12 hex digit) changeset id that `hg identify --id`
+    /// prints, not the full 40-digit hash.
+    async fn resolve_rev(&self, url: &Url, rev: &str) -> Result<String> {
+        Self::hg(&["identify", "--id", "--rev", rev, url.as_str()], None).await
+    }
+}
@@ -0,0 +1,105 @@
+use std::path::Path;
+use std::sync::Arc;
+
+use anyhow::Result;
+use url::Url;
+
+mod git;
+mod mercurial;
+
+pub use git::GitBackend;
+pub use mercurial::MercurialBackend;
+
+/// A version-control system capable of fetching a remote repo and checking
+/// out a specific revision. `Store` drives this to materialize repos on
+/// disk before `Repo::remote` reads their manifest; implementations only
+/// need to know how to talk to their own tool (`git`, `hg`, ...).
+pub trait Backend: std::fmt::Debug + Send + Sync {
+    /// Short, stable name used in config (`repo_type: hg`) and diagnostics.
+    fn name(&self) -> &'static str;
+
+    /// Whether this backend should handle `url`, used when no explicit
+    /// `repo_type` is configured.
+    fn supports(&self, url: &Url) -> bool;
+
+    /// Clone `url` at `rev` into `dest`, which does not yet exist.
+    async fn clone(&self, url: &Url, rev: &str, dest: &Path) -> Result<()>;
+
+    /// Fetch new revisions into an already-cloned `dest`.
+    async fn fetch(&self, dest: &Path) -> Result<()>;
+
+    /// Check out `rev` in the already-cloned `dest`.
+    async fn checkout(&self, dest: &Path, rev: &str) -> Result<()>;
+
+    /// Resolve `rev` to the commit id it points to on the remote, without
+    /// requiring a local clone. Which forms of `rev` resolve, and whether the
+    /// returned id is the full hash, depends on the backend.
+    async fn resolve_rev(&self, url: &Url, rev: &str) -> Result<String>;
+}
+
+/// All backends this build knows about, tried in order via `supports` when
+/// a repo doesn't pin an explicit `repo_type`. Third-party backends can't
+/// register into this static list, but the order here (most to least
+/// common) is the same order `select` searches.
+fn registry() -> Vec<Arc<dyn Backend>> {
+    vec![Arc::new(GitBackend), Arc::new(MercurialBackend)]
+}
+
+/// Pick the backend for `url`: an explicit `repo_type` hint (e.g. from
+/// `ConfigRemoteRepo::repo_type`) always wins, whatever the URL scheme;
+/// without one, the first backend whose `supports` probe matches is used.
+pub fn select(url: &Url, repo_type: Option<&str>) -> Result<Arc<dyn Backend>> {
+    let backends = registry();
+
+    if let Some(repo_type) = repo_type {
+        return backends
+            .into_iter()
+            .find(|backend| backend.name() == repo_type)
+            .ok_or_else(|| anyhow::anyhow!("unknown repo_type `{repo_type}`"));
+    }
+
+    backends
+        .into_iter()
+        .find(|backend| backend.supports(url))
+        .ok_or_else(|| anyhow::anyhow!("no VCS backend supports `{url}`"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn url(s: &str) -> Url {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn select_picks_git_for_an_https_url_by_default() {
+        let backend = select(&url("https://github.com/example/repo"), None).unwrap();
+        assert_eq!(backend.name(), "git");
+    }
+
+    #[test]
+    fn select_picks_mercurial_for_an_hg_url_by_default() {
+        let backend = select(&url("hg://example.com/repo"), None).unwrap();
+        assert_eq!(backend.name(), "hg");
+    }
+
+    #[test]
+    fn select_honors_an_explicit_repo_type_over_the_url_scheme() {
+        // An `https://` URL would otherwise default to git, but an explicit
+        // `repo_type: hg` hint (once `ConfigRemoteRepo` carries one through
+        // to here) must win regardless of what the scheme looks like.
+        let backend = select(&url("https://example.com/repo"), Some("hg")).unwrap();
+        assert_eq!(backend.name(), "hg");
+    }
+
+    #[test]
+    fn select_rejects_an_unknown_repo_type() {
+        assert!(select(&url("https://example.com/repo"), Some("svn")).is_err());
+    }
+
+    #[test]
+    fn select_rejects_a_scheme_no_backend_supports() {
+        assert!(select(&url("ftp://example.com/repo"), None).is_err());
+    }
+}